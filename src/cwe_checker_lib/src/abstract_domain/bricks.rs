@@ -0,0 +1,281 @@
+//! This module contains the Bricks domain (`BricksDomain`).
+//!
+//! Unlike the Character Inclusion domain, which only tracks which characters
+//! may or must occur somewhere in a string, the Bricks domain keeps track of
+//! the *order* in which fragments of a string occur.
+//!
+//! A brick `[S]^{min,max}` consists of a finite set `S` of concrete strings
+//! together with a lower and upper bound on how many elements of `S`, each
+//! chosen independently, are concatenated. An abstract string value is an
+//! ordered list of bricks, read left to right, e.g. the list
+//! `[{"ab"}]^{1,1} [{"c","d"}]^{2,3}` represents all strings of the form
+//! `"ab" + x_1 + x_2` up to `"ab" + x_1 + x_2 + x_3` where every `x_i` is
+//! either `"c"` or `"d"`.
+//!
+//! Since every operation on the domain can grow the brick list or the string
+//! sets inside of it without bound, the domain widens itself after every
+//! `merge`/`insert_string_domain` by normalizing the brick list and, if it is
+//! still too large, collapsing bricks to a wildcard or giving up to *Top*.
+//! The *Top* value stands for "any string".
+
+use std::collections::BTreeSet;
+
+use crate::prelude::*;
+
+use super::{AbstractDomain, DomainInsertion, HasTop};
+
+/// The maximum number of bricks a `BricksDomain` value may contain before
+/// it gets widened to *Top*.
+const MAX_NUM_BRICKS: usize = 5;
+/// The maximum number of concrete strings a single brick's string set may
+/// contain before the brick gets collapsed to a wildcard during widening.
+const MAX_BRICK_SET_SIZE: usize = 5;
+
+/// A single brick `[S]^{min,max}` of a `BricksDomain` value.
+///
+/// `Wildcard` represents a brick whose string set and repetition bounds have
+/// been given up on, i.e. it stands for an arbitrary (possibly empty) string
+/// fragment at this position.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub enum Brick {
+    /// A brick with a concrete, finite set of strings and concrete repetition bounds.
+    Strings {
+        /// The finite set `S` of concrete strings.
+        set: BTreeSet<String>,
+        /// The minimum number of elements of `set` that are concatenated.
+        min: u32,
+        /// The maximum number of elements of `set` that are concatenated.
+        max: u32,
+    },
+    /// A brick that has been widened to "an arbitrary string fragment".
+    Wildcard,
+}
+
+impl Brick {
+    /// Create the brick `[{string}]^{1,1}` for a single concrete string.
+    fn from_concrete_string(string: String) -> Brick {
+        let mut set = BTreeSet::new();
+        set.insert(string);
+        Brick::Strings { set, min: 1, max: 1 }
+    }
+
+    /// Create the empty brick `[{}]^{0,0}` used to pad shorter brick lists during `merge`.
+    fn empty() -> Brick {
+        Brick::Strings {
+            set: BTreeSet::new(),
+            min: 0,
+            max: 0,
+        }
+    }
+
+    /// Whether this brick is the empty brick `[{}]^{0,0}`.
+    fn is_empty(&self) -> bool {
+        matches!(self, Brick::Strings { set, min: 0, max: 0 } if set.is_empty())
+    }
+
+    /// Merge two bricks at the same position of two aligned brick lists.
+    ///
+    /// Unions the string sets and takes the min of the minima and the max of the maxima.
+    /// If either brick is a `Wildcard`, the result is a `Wildcard`.
+    fn merge(&self, other: &Brick) -> Brick {
+        match (self, other) {
+            (
+                Brick::Strings {
+                    set: self_set,
+                    min: self_min,
+                    max: self_max,
+                },
+                Brick::Strings {
+                    set: other_set,
+                    min: other_min,
+                    max: other_max,
+                },
+            ) => Brick::Strings {
+                set: self_set.union(other_set).cloned().collect(),
+                min: *self_min.min(other_min),
+                max: *self_max.max(other_max),
+            },
+            _ => Brick::Wildcard,
+        }
+    }
+}
+
+/// The `BricksDomain` is an abstract domain describing a string as an ordered
+/// list of bricks, each of which captures the possible contents of one
+/// fragment of the string together with how often that fragment may repeat.
+///
+/// The *Top* value stands for an arbitrary string.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub enum BricksDomain {
+    /// The *Top* value stands for an arbitrary string.
+    Top,
+    /// An ordered list of bricks describing the string.
+    Value(Vec<Brick>),
+}
+
+impl BricksDomain {
+    /// Unwraps the value from the Bricks domain.
+    pub fn unwrap_value(&self) -> Vec<Brick> {
+        match self {
+            BricksDomain::Value(bricks) => bricks.clone(),
+            BricksDomain::Top => panic!("Unexpected Top value for BricksDomain."),
+        }
+    }
+
+    /// Normalize a brick list and, if it is still too large afterwards,
+    /// widen it by collapsing bricks to `Wildcard`s or by giving up to *Top*.
+    ///
+    /// Normalization removes empty bricks and merges adjacent bricks that
+    /// share the same string set, e.g. `[{a}]^{1,1} [{a}]^{1,2}` becomes
+    /// `[{a}]^{2,3}`.
+    fn normalize_and_widen(bricks: Vec<Brick>) -> BricksDomain {
+        let mut normalized: Vec<Brick> = Vec::new();
+        for brick in bricks {
+            if brick.is_empty() {
+                continue;
+            }
+            match (normalized.last_mut(), &brick) {
+                (
+                    Some(Brick::Strings {
+                        set: prev_set,
+                        min: prev_min,
+                        max: prev_max,
+                    }),
+                    Brick::Strings { set, min, max },
+                ) if prev_set == set => {
+                    *prev_min += min;
+                    *prev_max += max;
+                }
+                _ => normalized.push(brick),
+            }
+        }
+
+        for brick in normalized.iter_mut() {
+            if let Brick::Strings { set, .. } = brick {
+                if set.len() > MAX_BRICK_SET_SIZE {
+                    *brick = Brick::Wildcard;
+                }
+            }
+        }
+
+        if normalized.len() > MAX_NUM_BRICKS {
+            return BricksDomain::Top;
+        }
+
+        BricksDomain::Value(normalized)
+    }
+}
+
+impl DomainInsertion for BricksDomain {
+    /// Concatenation of two strings is represented by appending the brick list
+    /// of `string_domain` to the brick list of `self`.
+    fn insert_string_domain(&self, string_domain: &Self) -> BricksDomain {
+        match (self, string_domain) {
+            (BricksDomain::Value(self_bricks), BricksDomain::Value(other_bricks)) => {
+                let mut bricks = self_bricks.clone();
+                bricks.extend(other_bricks.iter().cloned());
+                BricksDomain::normalize_and_widen(bricks)
+            }
+            _ => BricksDomain::Top,
+        }
+    }
+}
+
+impl From<String> for BricksDomain {
+    /// A concrete string is represented by the single brick `[{string}]^{1,1}`.
+    fn from(string: String) -> Self {
+        BricksDomain::Value(vec![Brick::from_concrete_string(string)])
+    }
+}
+
+impl AbstractDomain for BricksDomain {
+    /// Merge two brick lists by aligning them position by position, padding
+    /// the shorter list with empty bricks `[{}]^{0,0}`, and merging the
+    /// bricks at each position. The merged list is then normalized and, if
+    /// necessary, widened.
+    fn merge(&self, other: &Self) -> Self {
+        if self.is_top() || other.is_top() {
+            return Self::Top;
+        }
+        let self_bricks = self.unwrap_value();
+        let other_bricks = other.unwrap_value();
+        let len = self_bricks.len().max(other_bricks.len());
+        let mut merged = Vec::with_capacity(len);
+        for i in 0..len {
+            let self_brick = self_bricks.get(i).cloned().unwrap_or_else(Brick::empty);
+            let other_brick = other_bricks.get(i).cloned().unwrap_or_else(Brick::empty);
+            merged.push(self_brick.merge(&other_brick));
+        }
+        BricksDomain::normalize_and_widen(merged)
+    }
+
+    /// Check if the value is *Top*.
+    fn is_top(&self) -> bool {
+        matches!(self, Self::Top)
+    }
+}
+
+impl HasTop for BricksDomain {
+    /// Return a *Top* value.
+    fn top(&self) -> Self {
+        BricksDomain::Top
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brick(strings: &[&str], min: u32, max: u32) -> Brick {
+        Brick::Strings {
+            set: strings.iter().map(|s| s.to_string()).collect(),
+            min,
+            max,
+        }
+    }
+
+    #[test]
+    fn from_string() {
+        let domain = BricksDomain::from("abc".to_string());
+        assert_eq!(domain, BricksDomain::Value(vec![brick(&["abc"], 1, 1)]));
+    }
+
+    #[test]
+    fn concatenation() {
+        let first = BricksDomain::from("ab".to_string());
+        let second = BricksDomain::from("cd".to_string());
+        assert_eq!(
+            first.insert_string_domain(&second),
+            BricksDomain::Value(vec![brick(&["ab"], 1, 1), brick(&["cd"], 1, 1)])
+        );
+    }
+
+    #[test]
+    fn merging_aligns_and_pads() {
+        let first = BricksDomain::Value(vec![brick(&["a"], 1, 1), brick(&["b"], 1, 1)]);
+        let second = BricksDomain::Value(vec![brick(&["a"], 1, 1)]);
+
+        assert_eq!(
+            first.merge(&second),
+            BricksDomain::Value(vec![brick(&["a"], 1, 1), brick(&["b"], 0, 1)])
+        );
+        assert_eq!(first.merge(&BricksDomain::Top), BricksDomain::Top);
+    }
+
+    #[test]
+    fn normalization_merges_adjacent_identical_bricks() {
+        let bricks = vec![brick(&["a"], 1, 1), brick(&["a"], 1, 2)];
+        assert_eq!(
+            BricksDomain::normalize_and_widen(bricks),
+            BricksDomain::Value(vec![brick(&["a"], 2, 3)])
+        );
+    }
+
+    #[test]
+    fn widening_collapses_oversized_brick_lists() {
+        let bricks: Vec<Brick> = (0..MAX_NUM_BRICKS + 1)
+            .map(|i| brick(&[&i.to_string()], 1, 1))
+            .collect();
+        assert_eq!(BricksDomain::normalize_and_widen(bricks), BricksDomain::Top);
+    }
+}
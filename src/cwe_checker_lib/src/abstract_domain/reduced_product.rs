@@ -0,0 +1,202 @@
+//! This module contains the reduced product domain (`ReducedProduct<A, B>`).
+//!
+//! `State<T>` and `Context<'a, T>` (see `crate::analysis::abstract_string`) are parameterized
+//! over a single string abstract domain `T`, which limits precision to whatever that one
+//! abstraction can express. `ReducedProduct<A, B>` lifts two domains `A` and `B` into a single
+//! domain that runs both in lockstep: every operation (`merge`, `insert_string_domain`,
+//! `From<String>`) is performed on both components independently, pairwise.
+//!
+//! Running both components independently on its own would only ever be as precise as the more
+//! precise of the two. What makes this a *reduced* product is the `DomainReduction::reduce` step
+//! run after every operation, which lets one component refine the other using information the
+//! other component cannot express itself, e.g. a string length of exactly zero forces a character
+//! inclusion component to the empty set, or a concrete string proven by a bricks component
+//! tightens a length component to an exact interval.
+//!
+//! `reduce` is specific to the pair of component domains involved, so it is provided by the
+//! `DomainReduction` trait, which is implemented individually for the domain pairs used by the
+//! string analysis rather than generically for all `A, B`.
+
+use std::collections::HashSet;
+
+use crate::prelude::*;
+
+use super::{
+    AbstractDomain, BricksDomain, Brick, CharacterInclusionDomain, CharacterSet, DomainInsertion,
+    HasTop, LengthBound, StringLengthDomain,
+};
+
+/// The reduced product of two abstract domains `A` and `B`, tracking one value of each in
+/// lockstep and letting them refine each other via `DomainReduction::reduce`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ReducedProduct<A, B> {
+    /// The value of the first component domain.
+    pub first: A,
+    /// The value of the second component domain.
+    pub second: B,
+}
+
+/// Lets the components of a `ReducedProduct` refine each other using information only one of
+/// them can express.
+///
+/// Implemented individually for the domain pairs the string analysis instantiates, since the
+/// refinement rules are specific to the pair of domains involved.
+pub trait DomainReduction: Sized {
+    /// Refine the components of `self` using information from one another.
+    fn reduce(self) -> Self;
+}
+
+impl<A: AbstractDomain + HasTop, B: AbstractDomain + HasTop> ReducedProduct<A, B>
+where
+    Self: DomainReduction,
+{
+    /// Create a new reduced product value from its two components, applying the reduction step
+    /// once to let the components refine each other immediately.
+    pub fn new(first: A, second: B) -> Self {
+        ReducedProduct { first, second }.reduce()
+    }
+}
+
+impl<A: AbstractDomain + HasTop, B: AbstractDomain + HasTop> AbstractDomain for ReducedProduct<A, B>
+where
+    Self: DomainReduction,
+{
+    /// Merge the two components pairwise, then let them refine each other.
+    fn merge(&self, other: &Self) -> Self {
+        ReducedProduct {
+            first: self.first.merge(&other.first),
+            second: self.second.merge(&other.second),
+        }
+        .reduce()
+    }
+
+    /// A reduced product value is *Top* if both of its components are *Top*.
+    fn is_top(&self) -> bool {
+        self.first.is_top() && self.second.is_top()
+    }
+}
+
+impl<A: AbstractDomain + HasTop, B: AbstractDomain + HasTop> HasTop for ReducedProduct<A, B> {
+    /// Return the pair of the *Top* values of the two components.
+    fn top(&self) -> Self {
+        ReducedProduct {
+            first: self.first.top(),
+            second: self.second.top(),
+        }
+    }
+}
+
+impl<A: DomainInsertion + AbstractDomain + HasTop, B: DomainInsertion + AbstractDomain + HasTop>
+    DomainInsertion for ReducedProduct<A, B>
+where
+    Self: DomainReduction,
+{
+    /// Concatenate the two components pairwise, then let them refine each other.
+    fn insert_string_domain(&self, string_domain: &Self) -> Self {
+        ReducedProduct {
+            first: self.first.insert_string_domain(&string_domain.first),
+            second: self.second.insert_string_domain(&string_domain.second),
+        }
+        .reduce()
+    }
+}
+
+impl<A: From<String> + AbstractDomain + HasTop, B: From<String> + AbstractDomain + HasTop>
+    From<String> for ReducedProduct<A, B>
+where
+    Self: DomainReduction,
+{
+    /// Build both components from the same concrete string, then let them refine each other.
+    fn from(string: String) -> Self {
+        ReducedProduct {
+            first: A::from(string.clone()),
+            second: B::from(string),
+        }
+        .reduce()
+    }
+}
+
+impl DomainReduction for ReducedProduct<CharacterInclusionDomain, StringLengthDomain> {
+    /// If the length component proves the string is empty, the character inclusion component is
+    /// forced to the empty set (neither certainly nor possibly containing any character).
+    fn reduce(self) -> Self {
+        if self.first.is_top() || self.second.is_top() {
+            return self;
+        }
+        let (min, max) = self.second.unwrap_value();
+        if min == 0 && max == LengthBound::Value(0) {
+            let empty_set = CharacterSet::Value(HashSet::new());
+            return ReducedProduct {
+                first: CharacterInclusionDomain::Value((empty_set.clone(), empty_set)),
+                second: self.second,
+            };
+        }
+        self
+    }
+}
+
+impl DomainReduction for ReducedProduct<BricksDomain, StringLengthDomain> {
+    /// If the bricks component proves the string is a single concrete literal, i.e. it consists
+    /// of exactly one brick `[{s}]^{1,1}`, the length component is tightened to the exact
+    /// interval `[len(s), len(s)]`.
+    fn reduce(self) -> Self {
+        if self.first.is_top() {
+            return self;
+        }
+        let bricks = self.first.unwrap_value();
+        if let [Brick::Strings { set, min: 1, max: 1 }] = bricks.as_slice() {
+            if let Some(literal) = set.iter().next() {
+                if set.len() == 1 {
+                    let len = literal.len() as u64;
+                    return ReducedProduct {
+                        first: self.first,
+                        second: StringLengthDomain::Value {
+                            min: len,
+                            max: LengthBound::Value(len),
+                        },
+                    };
+                }
+            }
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_length_forces_empty_character_set() {
+        let ci = CharacterInclusionDomain::Value((
+            CharacterSet::Value(HashSet::from(['a'])),
+            CharacterSet::Value(HashSet::from(['a', 'b'])),
+        ));
+        let length = StringLengthDomain::Value {
+            min: 0,
+            max: LengthBound::Value(0),
+        };
+        let product = ReducedProduct::new(ci, length);
+        assert_eq!(
+            product.first,
+            CharacterInclusionDomain::Value((
+                CharacterSet::Value(HashSet::new()),
+                CharacterSet::Value(HashSet::new())
+            ))
+        );
+    }
+
+    #[test]
+    fn concrete_brick_tightens_length() {
+        let bricks = BricksDomain::from("abc".to_string());
+        let length = StringLengthDomain::Top;
+        let product = ReducedProduct::new(bricks, length);
+        assert_eq!(
+            product.second,
+            StringLengthDomain::Value {
+                min: 3,
+                max: LengthBound::Value(3)
+            }
+        );
+    }
+}
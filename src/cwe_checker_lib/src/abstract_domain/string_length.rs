@@ -0,0 +1,203 @@
+//! This module contains the String Length domain (`StringLengthDomain`).
+//!
+//! This domain tracks the possible byte-length of a string as an interval
+//! `[min, max]`, where `max` may be unbounded (`\[infinity\]`). The *Top*
+//! value is the interval `[0, infinity]`, i.e. "a string of any length".
+//!
+//! The following presents an example which shows how the domain works:
+//!  1. When a string is assigned to the domain its length is stored as a
+//!     single-point interval. e.g. "Hello" => [5, 5]
+//!  2. When two strings are concatenated, the two intervals are added
+//!     componentwise. e.g. [5, 5] + [0, 3] => [5, 8]
+//!  3. When two domains are merged, the join of the two intervals is taken,
+//!     i.e. the min of the minima and the max of the maxima.
+//!     e.g. [5, 5] v [0, 3] => [0, 5]
+//!
+//! `merge` is a plain, commutative join: the result does not depend on which side is "older", so
+//! it is safe to call at every branch join, not just at designated loop re-convergence points
+//! (this `Context`'s fixpoint engine does not distinguish the two). To still guarantee
+//! termination for a string length that grows with every iteration of a loop (e.g. repeated
+//! `strcat`), `merge` caps the upper bound at [`MAX_TRACKED_LENGTH`] and collapses anything
+//! beyond it to *infinity*, exactly like `BricksDomain::normalize_and_widen` caps the size of a
+//! brick list: either way, the chain of merges has finite height, so a fixpoint is reached after
+//! finitely many steps.
+
+use crate::prelude::*;
+
+use super::{AbstractDomain, DomainInsertion, HasTop};
+
+/// The largest upper bound `merge` tracks exactly before collapsing it to `Infinity`, bounding
+/// the height of the interval lattice so that a fixpoint computation using only `merge` (see the
+/// module documentation) is guaranteed to terminate.
+const MAX_TRACKED_LENGTH: u64 = 4096;
+
+/// An upper bound on a string's length, which may be unbounded.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub enum LengthBound {
+    /// A concrete upper bound.
+    Value(u64),
+    /// No known upper bound.
+    Infinity,
+}
+
+impl LengthBound {
+    /// Add two length bounds, saturating to `Infinity` if either side is `Infinity`
+    /// or the addition would overflow.
+    fn saturating_add(&self, other: &Self) -> Self {
+        match (self, other) {
+            (LengthBound::Value(self_value), LengthBound::Value(other_value)) => {
+                match self_value.checked_add(*other_value) {
+                    Some(sum) => LengthBound::Value(sum),
+                    None => LengthBound::Infinity,
+                }
+            }
+            _ => LengthBound::Infinity,
+        }
+    }
+}
+
+/// The `StringLengthDomain` is an abstract domain describing the possible
+/// byte-length of a string as an interval `[min, max]`.
+///
+/// The *Top* value is the interval `[0, infinity]`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub enum StringLengthDomain {
+    /// The *Top* value stands for the interval `[0, infinity]`.
+    Top,
+    /// The interval `[min, max]` of possible string lengths.
+    Value { min: u64, max: LengthBound },
+}
+
+impl StringLengthDomain {
+    /// Unwraps the value from the String Length domain.
+    pub fn unwrap_value(&self) -> (u64, LengthBound) {
+        match self {
+            StringLengthDomain::Value { min, max } => (*min, *max),
+            StringLengthDomain::Top => panic!("Unexpected Top value for StringLengthDomain."),
+        }
+    }
+
+}
+
+impl DomainInsertion for StringLengthDomain {
+    /// Concatenation of two strings adds their length intervals componentwise,
+    /// using saturating arithmetic on the upper bound.
+    fn insert_string_domain(&self, string_domain: &Self) -> StringLengthDomain {
+        match (self, string_domain) {
+            (
+                StringLengthDomain::Value {
+                    min: self_min,
+                    max: self_max,
+                },
+                StringLengthDomain::Value {
+                    min: other_min,
+                    max: other_max,
+                },
+            ) => StringLengthDomain::Value {
+                min: self_min.saturating_add(*other_min),
+                max: self_max.saturating_add(other_max),
+            },
+            _ => StringLengthDomain::Top,
+        }
+    }
+}
+
+impl From<String> for StringLengthDomain {
+    /// A concrete string of length `len` is represented by the interval `[len, len]`.
+    fn from(string: String) -> Self {
+        let len = string.len() as u64;
+        StringLengthDomain::Value {
+            min: len,
+            max: LengthBound::Value(len),
+        }
+    }
+}
+
+impl AbstractDomain for StringLengthDomain {
+    /// Merge two intervals by taking the min of the minima and the max of the maxima, collapsing
+    /// the upper bound to `Infinity` if it would otherwise exceed `MAX_TRACKED_LENGTH`.
+    ///
+    /// This is a plain, commutative join: the cap is an absolute threshold on the result, not a
+    /// comparison against which side is "older", so the result does not depend on merge order.
+    fn merge(&self, other: &Self) -> Self {
+        if self.is_top() || other.is_top() {
+            return Self::Top;
+        }
+        let (self_min, self_max) = self.unwrap_value();
+        let (other_min, other_max) = other.unwrap_value();
+        let min = self_min.min(other_min);
+        let max = match self_max.max(other_max) {
+            LengthBound::Value(value) if value > MAX_TRACKED_LENGTH => LengthBound::Infinity,
+            max => max,
+        };
+        StringLengthDomain::Value { min, max }
+    }
+
+    /// Check if the value is *Top*.
+    fn is_top(&self) -> bool {
+        matches!(self, Self::Top)
+    }
+}
+
+impl HasTop for StringLengthDomain {
+    /// Return a *Top* value, i.e. the interval `[0, infinity]`.
+    fn top(&self) -> Self {
+        StringLengthDomain::Top
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interval(min: u64, max: u64) -> StringLengthDomain {
+        StringLengthDomain::Value {
+            min,
+            max: LengthBound::Value(max),
+        }
+    }
+
+    #[test]
+    fn from_string() {
+        assert_eq!(
+            StringLengthDomain::from("hello".to_string()),
+            interval(5, 5)
+        );
+    }
+
+    #[test]
+    fn concatenation_adds_intervals() {
+        let first = interval(2, 4);
+        let second = interval(1, 1);
+        assert_eq!(first.insert_string_domain(&second), interval(3, 5));
+    }
+
+    #[test]
+    fn merging_joins_intervals() {
+        let first = interval(5, 5);
+        let second = interval(0, 3);
+        assert_eq!(first.merge(&second), interval(0, 5));
+        assert_eq!(second.merge(&first), interval(0, 5));
+        assert_eq!(first.merge(&StringLengthDomain::Top), StringLengthDomain::Top);
+    }
+
+    #[test]
+    fn merging_a_growing_upper_bound_does_not_widen_below_the_cap() {
+        let first = interval(0, 5);
+        let second = interval(0, 10);
+        assert_eq!(first.merge(&second), interval(0, 10));
+        assert_eq!(second.merge(&first), interval(0, 10));
+    }
+
+    #[test]
+    fn merging_collapses_an_upper_bound_beyond_the_cap_to_infinity() {
+        let first = interval(0, MAX_TRACKED_LENGTH);
+        let second = interval(0, MAX_TRACKED_LENGTH + 1);
+        let expected = StringLengthDomain::Value {
+            min: 0,
+            max: LengthBound::Infinity,
+        };
+        assert_eq!(first.merge(&second), expected);
+        assert_eq!(second.merge(&first), expected);
+    }
+}
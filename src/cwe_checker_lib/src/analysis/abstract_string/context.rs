@@ -6,6 +6,8 @@ use crate::{
 
 use std::{collections::HashSet, marker::PhantomData};
 
+use super::{liveness::Liveness, sinks};
+
 // contains trait implementations for the `Context` struct,
 // especially the implementation of the `interprocedural_fixpoint::Context` trait.
 mod trait_impls;
@@ -18,6 +20,9 @@ pub struct Context<'a, T> {
     /// The runtime memory image for reading global read-only variables.
     /// Note that values of writeable global memory segments are not tracked.
     pub runtime_memory_image: &'a RuntimeMemoryImage,
+    /// The result of a backward liveness analysis over `graph`, used to drop dead identifiers
+    /// from the tracked state as the fixpoint computation proceeds.
+    liveness: Liveness,
     /// Phantom data to resolve issue with unused generic type.
     _phantom_abstract_domain_data: PhantomData<T>,
 }
@@ -36,10 +41,12 @@ impl<'a, T> Context<'a, T> {
             .collect();
         let graph =
             crate::analysis::graph::get_program_cfg(&project.program, extern_symbol_tid_set);
+        let liveness = Liveness::compute(&graph, |call| sinks::consumed_identifiers(call, project));
         Context {
             graph,
             project,
             runtime_memory_image,
+            liveness,
             _phantom_abstract_domain_data: PhantomData,
         }
     }
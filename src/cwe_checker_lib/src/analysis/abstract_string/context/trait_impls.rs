@@ -1,12 +1,64 @@
 use super::*;
 use crate::{
-    abstract_domain::{AbstractDomain, HasTop},
-    analysis::abstract_string::state::State,
+    abstract_domain::{AbstractDomain, AbstractIdentifier, DomainInsertion, HasTop},
+    analysis::abstract_string::{
+        identifier::register_id,
+        sinks::{self, STRING_CONCAT_FUNCTIONS, STRING_SOURCE_FUNCTIONS},
+        state::State,
+    },
     intermediate_representation::*,
 };
 
-impl<'a, T: AbstractDomain + HasTop> crate::analysis::forward_interprocedural_fixpoint::Context<'a>
-    for Context<'a, T>
+impl<'a, T: AbstractDomain + HasTop + DomainInsertion + From<String>> Context<'a, T> {
+    /// If `expression` is a plain register access, return the `AbstractIdentifier` tracking its
+    /// string content. Every other expression (e.g. a computed address or a binary operation) is
+    /// conservatively treated as not trackable.
+    fn id_of_expression(&self, expression: &Expression) -> Option<AbstractIdentifier> {
+        match expression {
+            Expression::Var(var) => Some(register_id(var)),
+            _ => None,
+        }
+    }
+
+    /// Try to read a constant, NUL-terminated string out of read-only memory at the address
+    /// `expression` evaluates to.
+    fn string_constant_at(&self, expression: &Expression) -> Option<String> {
+        let Expression::Const(address) = expression else {
+            return None;
+        };
+        let address = address.try_to_u64().ok()?;
+        self.runtime_memory_image
+            .read_string_until_null_terminator(address)
+            .ok()
+            .map(|string| string.to_string())
+    }
+
+    /// Get the abstract string value of the `index`-th integer parameter of the current call,
+    /// according to the binary's standard calling convention. Returns `None` if the parameter
+    /// register cannot be determined or nothing is tracked for it, which is equivalent to it
+    /// being *Top*.
+    fn parameter_value(&self, state: &State<T>, index: usize) -> Option<T> {
+        self.project
+            .get_standard_calling_convention()
+            .and_then(|cconv| cconv.integer_parameter_register.get(index))
+            .and_then(|register| state.get(&register_id(register)))
+            .cloned()
+    }
+
+    /// Get the `AbstractIdentifier` that the destination buffer of a string-building libc call
+    /// (its first integer parameter, by libc convention) is tracked under.
+    fn destination_parameter_id(&self) -> Option<AbstractIdentifier> {
+        let register = self
+            .project
+            .get_standard_calling_convention()?
+            .integer_parameter_register
+            .first()?;
+        Some(register_id(register))
+    }
+}
+
+impl<'a, T: AbstractDomain + HasTop + DomainInsertion + From<String>>
+    crate::analysis::forward_interprocedural_fixpoint::Context<'a> for Context<'a, T>
 {
     type Value = State<T>;
 
@@ -18,50 +70,306 @@ impl<'a, T: AbstractDomain + HasTop> crate::analysis::forward_interprocedural_fi
         state1.merge(state2)
     }
 
+    /// Track string constants loaded from read-only memory and propagate tracked values across
+    /// plain register assignments. Any other assignment invalidates the destination, since its
+    /// new value cannot be expressed as a string abstract domain value.
     fn update_def(&self, state: &Self::Value, def: &Term<Def>) -> Option<Self::Value> {
-        todo!()
+        let mut new_state = state.clone();
+        match &def.term {
+            Def::Assign { var, value } => {
+                if let Some(string) = self.string_constant_at(value) {
+                    new_state.set(register_id(var), T::from(string));
+                } else if let Some(source_id) = self.id_of_expression(value) {
+                    match state.get(&source_id) {
+                        Some(value) => new_state.set(register_id(var), value.clone()),
+                        None => new_state.set_top(&register_id(var)),
+                    }
+                } else {
+                    new_state.set_top(&register_id(var));
+                }
+            }
+            Def::Load { var, address } => {
+                if let Some(string) = self.string_constant_at(address) {
+                    new_state.set(register_id(var), T::from(string));
+                } else {
+                    new_state.set_top(&register_id(var));
+                }
+            }
+            Def::Store { .. } => (),
+        }
+        if let Some(live) = self.liveness.live_after(&def.tid) {
+            new_state.retain_live(live);
+        }
+        Some(new_state)
     }
 
     fn update_jump(
         &self,
         value: &Self::Value,
         jump: &Term<Jmp>,
-        untaken_conditional: Option<&Term<Jmp>>,
-        target: &Term<Blk>,
+        _untaken_conditional: Option<&Term<Jmp>>,
+        _target: &Term<Blk>,
     ) -> Option<Self::Value> {
-        todo!()
+        let mut new_state = value.clone();
+        if let Some(live) = self.liveness.live_after(&jump.tid) {
+            new_state.retain_live(live);
+        }
+        Some(new_state)
     }
 
     fn update_call(
         &self,
         value: &Self::Value,
-        call: &Term<Jmp>,
-        target: &crate::analysis::graph::Node,
+        _call: &Term<Jmp>,
+        _target: &crate::analysis::graph::Node,
     ) -> Option<Self::Value> {
-        todo!()
+        Some(value.clone())
     }
 
     fn update_return(
         &self,
         value: Option<&Self::Value>,
         value_before_call: Option<&Self::Value>,
-        call_term: &Term<Jmp>,
-        return_term: &Term<Jmp>,
+        _call_term: &Term<Jmp>,
+        _return_term: &Term<Jmp>,
     ) -> Option<Self::Value> {
-        todo!()
+        match (value, value_before_call) {
+            (Some(value), Some(value_before_call)) => Some(value.merge(value_before_call)),
+            (Some(value), None) => Some(value.clone()),
+            (None, Some(value_before_call)) => Some(value_before_call.clone()),
+            (None, None) => None,
+        }
     }
 
+    /// Model the libc string-builder functions that matter for string reconstruction:
+    /// `strcpy`/`strncpy`/`sprintf`/`snprintf` overwrite the destination with their source string
+    /// (the copied string for `strcpy`/`strncpy`, the format string for `sprintf`/`snprintf`),
+    /// `strcat`/`strncat` concatenate their argument onto the destination's prior contents, and
+    /// every other (unknown) extern call invalidates the destination, since it may have
+    /// overwritten it with an arbitrary string.
     fn update_call_stub(&self, value: &Self::Value, call: &Term<Jmp>) -> Option<Self::Value> {
-        todo!()
+        let mut new_state = value.clone();
+        let Some(name) = sinks::called_extern_symbol_name(call, self.project) else {
+            return Some(new_state);
+        };
+        let Some(destination_id) = self.destination_parameter_id() else {
+            return Some(new_state);
+        };
+
+        if let Some((_, source_index)) = STRING_SOURCE_FUNCTIONS
+            .iter()
+            .find(|(function_name, _)| *function_name == name)
+        {
+            match self.parameter_value(value, *source_index) {
+                Some(source) => new_state.set(destination_id, source),
+                None => new_state.set_top(&destination_id),
+            }
+        } else if STRING_CONCAT_FUNCTIONS.contains(&name) {
+            match (
+                self.parameter_value(value, 0),
+                self.parameter_value(value, 1),
+            ) {
+                (Some(destination), Some(addition)) => {
+                    new_state.set(destination_id, destination.insert_string_domain(&addition))
+                }
+                _ => new_state.set_top(&destination_id),
+            }
+        } else {
+            new_state.set_top(&destination_id);
+        }
+        Some(new_state)
     }
 
     fn specialize_conditional(
         &self,
         value: &Self::Value,
-        condition: &Expression,
-        block_before_condition: &Term<Blk>,
-        is_true: bool,
+        _condition: &Expression,
+        _block_before_condition: &Term<Blk>,
+        _is_true: bool,
     ) -> Option<Self::Value> {
-        todo!()
+        Some(value.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        abstract_domain::BricksDomain,
+        analysis::{abstract_string::liveness::Liveness, graph::Graph},
+        intermediate_representation::{Bitvector, ByteSize, ExternSymbol},
+    };
+    use std::marker::PhantomData;
+
+    fn var(name: &str) -> Variable {
+        Variable {
+            name: name.to_string(),
+            size: ByteSize::new(8),
+            is_temp: false,
+        }
+    }
+
+    fn id(name: &str) -> AbstractIdentifier {
+        register_id(&var(name))
+    }
+
+    /// Build a `Context` with an empty CFG and an empty liveness result. The empty liveness
+    /// result records no program points, so `update_def` prunes no identifiers.
+    fn mock_context<'a>(
+        project: &'a Project,
+        memory_image: &'a RuntimeMemoryImage,
+    ) -> Context<'a, BricksDomain> {
+        Context {
+            graph: Graph::new(),
+            project,
+            runtime_memory_image: memory_image,
+            liveness: Liveness::compute(&Graph::new(), |_| Vec::new()),
+            _phantom_abstract_domain_data: PhantomData,
+        }
+    }
+
+    /// Register `name` as an extern symbol of `project` and return a call jump targeting it.
+    fn call_to(name: &str, project: &mut Project) -> Term<Jmp> {
+        let mut symbol = ExternSymbol::mock();
+        symbol.name = name.to_string();
+        let target = symbol.tid.clone();
+        project.program.term.extern_symbols.push(symbol);
+        Term {
+            tid: Tid::new(format!("call_{name}")),
+            term: Jmp::Call {
+                target,
+                return_: None,
+            },
+        }
+    }
+
+    #[test]
+    fn update_def_propagates_a_plain_copy() {
+        let project = Project::mock();
+        let memory_image = RuntimeMemoryImage::mock();
+        let context = mock_context(&project, &memory_image);
+
+        let mut state = State::new();
+        state.set(id("RDI"), BricksDomain::from("hi".to_string()));
+        let def = Term {
+            tid: Tid::new("def"),
+            term: Def::Assign {
+                var: var("RAX"),
+                value: Expression::Var(var("RDI")),
+            },
+        };
+
+        let new_state = context.update_def(&state, &def).unwrap();
+        assert_eq!(
+            new_state.get(&id("RAX")),
+            Some(&BricksDomain::from("hi".to_string()))
+        );
+    }
+
+    #[test]
+    fn update_def_invalidates_a_computed_assignment() {
+        let project = Project::mock();
+        let memory_image = RuntimeMemoryImage::mock();
+        let context = mock_context(&project, &memory_image);
+
+        let mut state = State::new();
+        state.set(id("RAX"), BricksDomain::from("stale".to_string()));
+        let def = Term {
+            tid: Tid::new("def"),
+            term: Def::Assign {
+                var: var("RAX"),
+                value: Expression::Const(Bitvector::from_u64(0)),
+            },
+        };
+
+        let new_state = context.update_def(&state, &def).unwrap();
+        assert_eq!(new_state.get(&id("RAX")), None);
+    }
+
+    #[test]
+    fn update_def_invalidates_a_load_from_an_unresolvable_address() {
+        let project = Project::mock();
+        let memory_image = RuntimeMemoryImage::mock();
+        let context = mock_context(&project, &memory_image);
+
+        let def = Term {
+            tid: Tid::new("def"),
+            term: Def::Load {
+                var: var("RAX"),
+                address: Expression::Var(var("RDI")),
+            },
+        };
+
+        let new_state = context.update_def(&State::new(), &def).unwrap();
+        assert_eq!(new_state.get(&id("RAX")), None);
+    }
+
+    #[test]
+    fn update_call_stub_models_strcpy_as_a_source_function() {
+        let mut project = Project::mock();
+        let call = call_to("strcpy", &mut project);
+        let memory_image = RuntimeMemoryImage::mock();
+        let context = mock_context(&project, &memory_image);
+
+        let mut state = State::new();
+        state.set(id("RSI"), BricksDomain::from("copied".to_string()));
+
+        let new_state = context.update_call_stub(&state, &call).unwrap();
+        assert_eq!(
+            new_state.get(&id("RDI")),
+            Some(&BricksDomain::from("copied".to_string()))
+        );
+    }
+
+    #[test]
+    fn update_call_stub_models_strcat_as_concatenation() {
+        let mut project = Project::mock();
+        let call = call_to("strcat", &mut project);
+        let memory_image = RuntimeMemoryImage::mock();
+        let context = mock_context(&project, &memory_image);
+
+        let mut state = State::new();
+        state.set(id("RDI"), BricksDomain::from("ab".to_string()));
+        state.set(id("RSI"), BricksDomain::from("cd".to_string()));
+
+        let new_state = context.update_call_stub(&state, &call).unwrap();
+        assert_eq!(
+            new_state.get(&id("RDI")),
+            Some(&BricksDomain::from("ab".to_string()).insert_string_domain(&BricksDomain::from("cd".to_string())))
+        );
+    }
+
+    #[test]
+    fn update_call_stub_models_snprintf_with_its_own_source_index() {
+        let mut project = Project::mock();
+        let call = call_to("snprintf", &mut project);
+        let memory_image = RuntimeMemoryImage::mock();
+        let context = mock_context(&project, &memory_image);
+
+        let mut state = State::new();
+        // Index 1 is `size_t size`, not the format string. If `snprintf` wrongly shared
+        // `sprintf`'s index 1, this test would observe "wrong" instead of "fmt".
+        state.set(id("RSI"), BricksDomain::from("wrong".to_string()));
+        state.set(id("RDX"), BricksDomain::from("fmt".to_string()));
+
+        let new_state = context.update_call_stub(&state, &call).unwrap();
+        assert_eq!(
+            new_state.get(&id("RDI")),
+            Some(&BricksDomain::from("fmt".to_string()))
+        );
+    }
+
+    #[test]
+    fn update_call_stub_invalidates_an_unknown_function() {
+        let mut project = Project::mock();
+        let call = call_to("memcpy", &mut project);
+        let memory_image = RuntimeMemoryImage::mock();
+        let context = mock_context(&project, &memory_image);
+
+        let mut state = State::new();
+        state.set(id("RDI"), BricksDomain::from("stale".to_string()));
+
+        let new_state = context.update_call_stub(&state, &call).unwrap();
+        assert_eq!(new_state.get(&id("RDI")), None);
     }
 }
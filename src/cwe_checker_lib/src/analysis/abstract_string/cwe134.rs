@@ -0,0 +1,142 @@
+//! CWE-134 (Uncontrolled Format String) detection built on the string analysis.
+//!
+//! At every call to a recognized format-string consuming libc function (`printf`, `fprintf`,
+//! `sprintf`, `syslog`), this checker queries the fixpoint result of a `Context` run with
+//! `T = ReducedProduct<BricksDomain, StringLengthDomain>` for the value of the format-string
+//! argument. A plain character-inclusion fact is not precise enough here: merging two distinct,
+//! individually safe literals (e.g. `"%s\n"` on one branch and `"%d\n"` on another) only tells a
+//! character-inclusion domain that the certainly-contained set shrank, which looks the same as
+//! tainted input widening the possibly-contained set. The bricks component instead keeps the
+//! *set* of literals distinct for as long as it can, so a format string is only treated as
+//! uncontrolled once that set has actually been given up on (collapsed to a `Wildcard` brick, or
+//! to *Top* entirely) rather than merely merged with another known literal.
+//!
+//! Note: this crate snapshot does not contain the `checkers`/`CweModule`/`CweWarning` scaffolding
+//! that the other CWE checks plug into, so `check_format_strings` is a self-contained function
+//! returning its own warning type rather than registering itself with that (absent) machinery.
+
+use std::collections::HashMap;
+
+use crate::{
+    abstract_domain::{AbstractDomain, Brick, BricksDomain, ReducedProduct, StringLengthDomain},
+    intermediate_representation::{Jmp, Term, Tid},
+};
+
+use super::{
+    context::Context,
+    identifier::register_id,
+    sinks::{called_extern_symbol_name, FORMAT_STRING_FUNCTIONS},
+    state::State,
+};
+
+/// The abstract domain the CWE-134 checker tracks the format-string argument with.
+type FormatStringDomain = ReducedProduct<BricksDomain, StringLengthDomain>;
+
+/// A CWE-134 finding: the call at `call_tid` passes a format string that cannot be proven to be a
+/// constant literal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cwe134Warning {
+    /// The `Tid` of the call passing the uncontrolled format string.
+    pub call_tid: Tid,
+}
+
+/// Check every call to a recognized format-string function in `context.project` for CWE-134,
+/// using `results`, the fixpoint computed by running `context` to completion (keyed by the `Tid`
+/// of the call jump at which the state was observed).
+pub fn check_format_strings(
+    context: &Context<'_, FormatStringDomain>,
+    results: &HashMap<Tid, State<FormatStringDomain>>,
+) -> Vec<Cwe134Warning> {
+    context
+        .project
+        .program
+        .term
+        .subs
+        .iter()
+        .flat_map(|sub| sub.term.blocks.iter())
+        .flat_map(|block| block.term.jmps.iter())
+        .filter_map(|jump| check_call(context, results, jump))
+        .collect()
+}
+
+/// Check a single call for CWE-134, if it targets a recognized format-string function.
+fn check_call(
+    context: &Context<'_, FormatStringDomain>,
+    results: &HashMap<Tid, State<FormatStringDomain>>,
+    jump: &Term<Jmp>,
+) -> Option<Cwe134Warning> {
+    let name = called_extern_symbol_name(jump, context.project)?;
+    let (_, index) = FORMAT_STRING_FUNCTIONS
+        .iter()
+        .find(|(function_name, _)| *function_name == name)?;
+    let register = context
+        .project
+        .get_standard_calling_convention()?
+        .integer_parameter_register
+        .get(*index)?;
+    let format_string_value = results
+        .get(&jump.tid)
+        .and_then(|state| state.get(&register_id(register)));
+    if is_uncontrolled(format_string_value) {
+        Some(Cwe134Warning {
+            call_tid: jump.tid.clone(),
+        })
+    } else {
+        None
+    }
+}
+
+/// A format-string argument is uncontrolled if its value is unknown (*Top*), or if the bricks
+/// component of its value has given up on enumerating the possible literals, i.e. it is *Top* or
+/// contains a `Wildcard` brick. As long as every brick still carries a concrete, finite set of
+/// strings, every value the format string could take at runtime is one the analysis has fully
+/// enumerated from the binary's own constants, regardless of how many distinct literals were
+/// merged together to get there or whether any of them happen to contain a `%`.
+fn is_uncontrolled(value: Option<&FormatStringDomain>) -> bool {
+    let Some(value) = value else {
+        return true;
+    };
+    if value.first.is_top() {
+        return true;
+    }
+    value
+        .first
+        .unwrap_value()
+        .iter()
+        .any(|brick| matches!(brick, Brick::Wildcard))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn product(bricks: BricksDomain) -> FormatStringDomain {
+        ReducedProduct::new(bricks, StringLengthDomain::Top)
+    }
+
+    #[test]
+    fn top_is_uncontrolled() {
+        assert!(is_uncontrolled(None));
+        assert!(is_uncontrolled(Some(&product(BricksDomain::Top))));
+    }
+
+    #[test]
+    fn constant_literal_is_not_uncontrolled() {
+        let value = product(BricksDomain::from("abc".to_string()));
+        assert!(!is_uncontrolled(Some(&value)));
+    }
+
+    #[test]
+    fn wildcard_brick_is_uncontrolled() {
+        let value = product(BricksDomain::Value(vec![Brick::Wildcard]));
+        assert!(is_uncontrolled(Some(&value)));
+    }
+
+    #[test]
+    fn merge_of_two_distinct_safe_literals_is_not_uncontrolled() {
+        let first = BricksDomain::from("%s\n".to_string());
+        let second = BricksDomain::from("%d\n".to_string());
+        let merged = product(first.merge(&second));
+        assert!(!is_uncontrolled(Some(&merged)));
+    }
+}
@@ -0,0 +1,17 @@
+use crate::{
+    abstract_domain::{AbstractIdentifier, AbstractLocation},
+    intermediate_representation::{Tid, Variable},
+};
+
+/// Build the `AbstractIdentifier` under which the string content of `var` is tracked.
+///
+/// Register contents are tracked flow-sensitively for the whole analysis run rather than
+/// relative to a call site or stack frame, since (unlike pointer provenance) the string content
+/// of a register does not need to be disambiguated between recursive calls for the purposes of
+/// this analysis.
+pub(crate) fn register_id(var: &Variable) -> AbstractIdentifier {
+    AbstractIdentifier::new(
+        Tid::new("string_analysis_registers"),
+        AbstractLocation::Register(var.clone()),
+    )
+}
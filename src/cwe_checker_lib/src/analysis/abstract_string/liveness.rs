@@ -0,0 +1,296 @@
+//! Backward liveness analysis for the identifiers tracked by the string analysis.
+//!
+//! The `strings_tracked` map of `State<T>` would otherwise accumulate an entry for every
+//! identifier ever written, long after the string it describes can no longer reach a
+//! string-consuming call (a *sink*). This module computes, for every `Def`/`Jmp` in the program,
+//! the set of identifiers whose value is still needed by some future sink, by walking the CFG in
+//! reverse execution order to a fixpoint: an identifier is live if it is read by a sink or copied
+//! into another live identifier, and it is killed at the point it is overwritten.
+//! `Context::update_def`/`update_jump` use the result to drop dead identifiers from
+//! `strings_tracked`, bounding its size by the identifiers that are actually still live rather
+//! than by every identifier ever seen.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use petgraph::{graph::NodeIndex, visit::EdgeRef, Direction};
+
+use crate::{
+    abstract_domain::AbstractIdentifier,
+    analysis::graph::{Graph, Node},
+    intermediate_representation::{Blk, Def, Expression, Jmp, Term, Tid},
+};
+
+use super::identifier::register_id;
+
+/// The result of a backward liveness analysis over the string analysis CFG.
+pub(crate) struct Liveness {
+    /// For every `Def`/`Jmp` term (identified by its `Tid`), the set of identifiers that are live
+    /// immediately *after* that term has executed.
+    live_after: HashMap<Tid, HashSet<AbstractIdentifier>>,
+}
+
+impl Liveness {
+    /// Compute liveness for every `Def`/`Jmp` in `graph`.
+    ///
+    /// `is_sink` returns the identifiers a given call jump reads; those identifiers are live
+    /// immediately before the call regardless of anything else.
+    pub(crate) fn compute<'a>(
+        graph: &Graph<'a>,
+        is_sink: impl Fn(&Term<Jmp>) -> Vec<AbstractIdentifier>,
+    ) -> Self {
+        let mut live_before_node: HashMap<NodeIndex, HashSet<AbstractIdentifier>> = HashMap::new();
+        let mut worklist: VecDeque<NodeIndex> = graph.node_indices().collect();
+
+        while let Some(node) = worklist.pop_front() {
+            let live_out = Self::live_out(graph, node, &live_before_node);
+            let live_in = Self::transfer_node(&graph[node], live_out, &is_sink, None);
+            if live_before_node.get(&node) != Some(&live_in) {
+                live_before_node.insert(node, live_in);
+                worklist.extend(
+                    graph
+                        .edges_directed(node, Direction::Incoming)
+                        .map(|edge| edge.source()),
+                );
+            }
+        }
+
+        let mut live_after = HashMap::new();
+        for node in graph.node_indices() {
+            let live_out = Self::live_out(graph, node, &live_before_node);
+            Self::transfer_node(&graph[node], live_out, &is_sink, Some(&mut live_after));
+        }
+
+        Liveness { live_after }
+    }
+
+    /// The set of identifiers live immediately after `tid` has executed, or `None` if `tid` is
+    /// not a point the analysis recorded (e.g. it belongs to a node the CFG does not expose defs
+    /// or jumps for).
+    pub(crate) fn live_after(&self, tid: &Tid) -> Option<&HashSet<AbstractIdentifier>> {
+        self.live_after.get(tid)
+    }
+
+    /// The identifiers live at the exit of `node`, i.e. the union of the live-before sets of all
+    /// of its CFG successors.
+    fn live_out(
+        graph: &Graph,
+        node: NodeIndex,
+        live_before_node: &HashMap<NodeIndex, HashSet<AbstractIdentifier>>,
+    ) -> HashSet<AbstractIdentifier> {
+        graph
+            .edges_directed(node, Direction::Outgoing)
+            .flat_map(|edge| {
+                live_before_node
+                    .get(&edge.target())
+                    .cloned()
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// Run the backward transfer function for one CFG node starting from `live` (the identifiers
+    /// live at its exit), returning the identifiers live at its entry.
+    ///
+    /// If `record` is given, the live-after set of every `Def`/`Jmp` the node contains is
+    /// inserted into it.
+    fn transfer_node(
+        node: &Node,
+        mut live: HashSet<AbstractIdentifier>,
+        is_sink: &impl Fn(&Term<Jmp>) -> Vec<AbstractIdentifier>,
+        mut record: Option<&mut HashMap<Tid, HashSet<AbstractIdentifier>>>,
+    ) -> HashSet<AbstractIdentifier> {
+        let Some(block) = Self::block_of(node) else {
+            return live;
+        };
+        for jump in block.term.jmps.iter().rev() {
+            if let Some(map) = record.as_deref_mut() {
+                map.insert(jump.tid.clone(), live.clone());
+            }
+            live.extend(is_sink(jump));
+        }
+        for def in block.term.defs.iter().rev() {
+            if let Some(map) = record.as_deref_mut() {
+                map.insert(def.tid.clone(), live.clone());
+            }
+            Self::transfer_def(&mut live, def);
+        }
+        live
+    }
+
+    /// Kill the identifier defined by `def` and, if it is a plain copy from another identifier,
+    /// propagate liveness to the source identifier.
+    fn transfer_def(live: &mut HashSet<AbstractIdentifier>, def: &Term<Def>) {
+        let (dst, src_expression) = match &def.term {
+            Def::Assign { var, value } => (var, Some(value)),
+            Def::Load { var, .. } => (var, None),
+            Def::Store { .. } => return,
+        };
+        let was_live = live.remove(&register_id(dst));
+        if was_live {
+            if let Some(Expression::Var(src)) = src_expression {
+                live.insert(register_id(src));
+            }
+        }
+    }
+
+    /// The `Blk` a CFG node corresponds to, if any (call-related nodes have no defs of their own).
+    fn block_of<'a>(node: &Node<'a>) -> Option<&'a Term<Blk>> {
+        match node {
+            Node::BlkStart(block, _) | Node::BlkEnd(block, _) => Some(block),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        analysis::graph::Edge,
+        intermediate_representation::{Bitvector, ByteSize, Sub, Variable},
+    };
+
+    fn var(name: &str) -> Variable {
+        Variable {
+            name: name.to_string(),
+            size: ByteSize::new(8),
+            is_temp: false,
+        }
+    }
+
+    fn id(name: &str) -> AbstractIdentifier {
+        register_id(&var(name))
+    }
+
+    fn assign(dst: &str, src: &str) -> Term<Def> {
+        Term {
+            tid: Tid::new(format!("def_{dst}")),
+            term: Def::Assign {
+                var: var(dst),
+                value: Expression::Var(var(src)),
+            },
+        }
+    }
+
+    fn overwrite(dst: &str) -> Term<Def> {
+        Term {
+            tid: Tid::new(format!("def_{dst}")),
+            term: Def::Assign {
+                var: var(dst),
+                value: Expression::Const(Bitvector::from_u64(0)),
+            },
+        }
+    }
+
+    #[test]
+    fn copy_propagates_liveness_to_source() {
+        // `rax := rbx`, with `rax` live afterwards, should make `rbx` live beforehand instead.
+        let def = assign("rax", "rbx");
+        let mut live = HashSet::from([id("rax")]);
+        Liveness::transfer_def(&mut live, &def);
+        assert_eq!(live, HashSet::from([id("rbx")]));
+    }
+
+    #[test]
+    fn overwrite_kills_liveness() {
+        // `rax := 0` does not read any register, so it kills `rax`'s liveness without reviving
+        // anything else.
+        let def = overwrite("rax");
+        let mut live = HashSet::from([id("rax")]);
+        Liveness::transfer_def(&mut live, &def);
+        assert!(live.is_empty());
+    }
+
+    #[test]
+    fn assignment_to_a_dead_destination_does_not_propagate() {
+        // `rax := rbx` with `rax` already dead must not make `rbx` live: the assignment's result
+        // is never used, so `rbx`'s prior value is not read by it either.
+        let def = assign("rax", "rbx");
+        let mut live = HashSet::new();
+        Liveness::transfer_def(&mut live, &def);
+        assert!(live.is_empty());
+    }
+
+    #[test]
+    fn live_out_is_the_union_of_both_successors_live_before_sets() {
+        // A minimal synthetic CFG: `entry` branches to `left` and `right`, which are left empty
+        // since `live_out` only consults the already-computed live-before sets of `entry`'s
+        // successors, not their contents.
+        let block = Term {
+            tid: Tid::new("blk"),
+            term: Blk {
+                defs: Vec::new(),
+                jmps: Vec::new(),
+            },
+        };
+        let sub = Term {
+            tid: Tid::new("sub"),
+            term: Sub {
+                name: "sub".to_string(),
+                blocks: Vec::new(),
+            },
+        };
+
+        let mut graph = Graph::new();
+        let entry = graph.add_node(Node::BlkStart(&block, &sub));
+        let left = graph.add_node(Node::BlkStart(&block, &sub));
+        let right = graph.add_node(Node::BlkStart(&block, &sub));
+        graph.add_edge(entry, left, Edge::Block);
+        graph.add_edge(entry, right, Edge::Block);
+
+        let mut live_before_node = HashMap::new();
+        live_before_node.insert(left, HashSet::from([id("rax")]));
+        live_before_node.insert(right, HashSet::from([id("rbx")]));
+
+        let live_out = Liveness::live_out(&graph, entry, &live_before_node);
+        assert_eq!(live_out, HashSet::from([id("rax"), id("rbx")]));
+    }
+
+    #[test]
+    fn compute_propagates_a_sink_use_backwards_through_a_copy() {
+        // `rax := rbx` followed by a call that reads `rax` as a sink: `rbx` must end up live
+        // before the assignment, since it is the value the sink ultimately observes.
+        let call = Term {
+            tid: Tid::new("call"),
+            term: Jmp::Call {
+                target: Tid::new("callee"),
+                return_: None,
+            },
+        };
+        let def = assign("rax", "rbx");
+        let block = Term {
+            tid: Tid::new("blk"),
+            term: Blk {
+                defs: vec![def.clone()],
+                jmps: vec![call.clone()],
+            },
+        };
+        let sub = Term {
+            tid: Tid::new("sub"),
+            term: Sub {
+                name: "sub".to_string(),
+                blocks: vec![block.clone()],
+            },
+        };
+
+        let mut graph = Graph::new();
+        graph.add_node(Node::BlkStart(&block, &sub));
+
+        let is_sink = |jump: &Term<Jmp>| -> Vec<AbstractIdentifier> {
+            if jump.tid == Tid::new("call") {
+                vec![id("rax")]
+            } else {
+                Vec::new()
+            }
+        };
+        let liveness = Liveness::compute(&graph, is_sink);
+
+        assert_eq!(liveness.live_after(&def.tid), Some(&HashSet::from([id("rax")])));
+        assert_eq!(liveness.live_after(&call.tid), Some(&HashSet::new()));
+        let node = graph.node_indices().next().unwrap();
+        assert_eq!(
+            Liveness::transfer_node(&graph[node], HashSet::new(), &is_sink, None),
+            HashSet::from([id("rbx")])
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! Recognition of the libc string-building calls modeled by the string analysis.
+//!
+//! This is shared between `Context::update_call_stub`, which needs the actual abstract values of
+//! the consumed arguments, and the `liveness` module, which only needs to know *which*
+//! identifiers a call consumes in order to keep them alive.
+
+use crate::{
+    abstract_domain::AbstractIdentifier,
+    intermediate_representation::{Jmp, Project, Term},
+};
+
+use super::identifier::register_id;
+
+/// libc functions that overwrite their destination with a copy of one of their string arguments,
+/// together with the index of that source argument among the function's integer parameters.
+/// `sprintf`/`snprintf` belong here, not in `STRING_CONCAT_FUNCTIONS`: unlike `strcat`/`strncat`,
+/// they overwrite their destination rather than appending to its prior contents, so they are
+/// modeled the same way as `strcpy`/`strncpy` (destination := source), just with their own source
+/// index, since `snprintf`'s format string is its third argument (index 2), not its second
+/// (`size_t size`, index 1) the way `sprintf`'s is.
+pub(crate) const STRING_SOURCE_FUNCTIONS: &[(&str, usize)] = &[
+    ("strcpy", 1),
+    ("strncpy", 1),
+    ("sprintf", 1),
+    ("snprintf", 2),
+];
+/// libc functions that append one of their string arguments onto their destination.
+pub(crate) const STRING_CONCAT_FUNCTIONS: &[&str] = &["strcat", "strncat"];
+/// libc functions that interpret one of their arguments as a format string, together with the
+/// index of that argument among the function's integer parameters. Used by both the CWE-134
+/// checker, which reads the format-string argument's abstract value, and `consumed_identifiers`
+/// below, which must report that same argument as live so the liveness analysis does not prune it
+/// before the checker gets to read it.
+pub(crate) const FORMAT_STRING_FUNCTIONS: &[(&str, usize)] = &[
+    ("printf", 0),
+    ("fprintf", 1),
+    ("sprintf", 1),
+    ("syslog", 1),
+];
+
+/// Look up the name of the extern symbol that `call` targets, if any.
+pub(crate) fn called_extern_symbol_name<'a>(
+    call: &Term<Jmp>,
+    project: &'a Project,
+) -> Option<&'a str> {
+    let target_tid = match &call.term {
+        Jmp::Call { target, .. } => Some(target),
+        _ => None,
+    }?;
+    project
+        .program
+        .term
+        .extern_symbols
+        .iter()
+        .find(|symbol| &symbol.tid == target_tid)
+        .map(|symbol| symbol.name.as_str())
+}
+
+/// The identifiers read (but not overwritten) by a recognized string-building or format-string
+/// consuming libc call, i.e. the identifiers a backward liveness analysis must keep alive across
+/// such a call.
+pub(crate) fn consumed_identifiers(call: &Term<Jmp>, project: &Project) -> Vec<AbstractIdentifier> {
+    let Some(name) = called_extern_symbol_name(call, project) else {
+        return Vec::new();
+    };
+    let Some(cconv) = project.get_standard_calling_convention() else {
+        return Vec::new();
+    };
+
+    let mut indices: Vec<usize> = Vec::new();
+    if let Some((_, source_index)) = STRING_SOURCE_FUNCTIONS
+        .iter()
+        .find(|(function_name, _)| *function_name == name)
+    {
+        indices.push(*source_index);
+    }
+    if STRING_CONCAT_FUNCTIONS.contains(&name) {
+        indices.extend([0, 1]);
+    }
+    if let Some((_, format_index)) = FORMAT_STRING_FUNCTIONS
+        .iter()
+        .find(|(function_name, _)| *function_name == name)
+    {
+        indices.push(*format_index);
+    }
+    indices.sort_unstable();
+    indices.dedup();
+
+    indices
+        .iter()
+        .filter_map(|&index| cconv.integer_parameter_register.get(index))
+        .map(register_id)
+        .collect()
+}
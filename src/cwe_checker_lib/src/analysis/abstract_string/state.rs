@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use crate::{
     abstract_domain::{AbstractDomain, AbstractIdentifier, HasTop},
@@ -6,25 +6,116 @@ use crate::{
 };
 
 /// Contains all information known about the state of a program at a specific point of time.
+///
+/// The state tracks, for every `AbstractIdentifier` of interest, the current abstract value of
+/// the string it denotes. An identifier that is not a key of `strings_tracked` is implicitly
+/// *Top*, i.e. nothing is known about the corresponding string. This means that forgetting a
+/// value (e.g. because an unknown external function could have overwritten it) can either be
+/// represented by removing the identifier from the map or by inserting an explicit `T::Top`
+/// value; both are treated as equivalent by `is_top`.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
 pub struct State<T: AbstractDomain + HasTop> {
     strings_tracked: HashMap<AbstractIdentifier, T>,
 }
 
 impl<T: AbstractDomain + HasTop> AbstractDomain for State<T> {
+    /// Merge two states.
+    ///
+    /// For an identifier tracked on both sides, the merge of the two values is tracked. An
+    /// identifier tracked on only one side is dropped: a missing key stands for *Top*, so such an
+    /// identifier is implicitly already being merged with *Top* on the side that does not track
+    /// it, and the result of that merge is *Top* again, i.e. no entry at all.
     fn merge(&self, other: &Self) -> Self {
-        todo!()
+        let mut strings_tracked = HashMap::new();
+        for (id, self_value) in self.strings_tracked.iter() {
+            if let Some(other_value) = other.strings_tracked.get(id) {
+                strings_tracked.insert(id.clone(), self_value.merge(other_value));
+            }
+        }
+        State { strings_tracked }
     }
 
+    /// Returns `true` if every tracked value is *Top*.
+    ///
+    /// In particular, a state that tracks no identifiers at all is *Top*.
     fn is_top(&self) -> bool {
-        todo!()
+        self.strings_tracked.values().all(|value| value.is_top())
     }
 }
 
 impl<T: AbstractDomain + HasTop> State<T> {
+    /// Generate a new, empty state that tracks no strings.
     pub fn new() -> State<T> {
         State {
             strings_tracked: HashMap::new(),
         }
     }
+
+    /// Get the abstract value tracked for `id`, or `None` if `id` is not (yet) tracked,
+    /// which is equivalent to `id` being mapped to *Top*.
+    pub fn get(&self, id: &AbstractIdentifier) -> Option<&T> {
+        self.strings_tracked.get(id)
+    }
+
+    /// Track `value` for `id`, overwriting any value tracked for `id` beforehand.
+    pub fn set(&mut self, id: AbstractIdentifier, value: T) {
+        self.strings_tracked.insert(id, value);
+    }
+
+    /// Forget everything tracked for `id`, i.e. treat it as *Top* from now on.
+    pub fn set_top(&mut self, id: &AbstractIdentifier) {
+        self.strings_tracked.remove(id);
+    }
+
+    /// Drop every tracked identifier that is not contained in `live`.
+    ///
+    /// Used to bound the size of `strings_tracked` by the identifiers a liveness analysis has
+    /// determined can still reach a future string-consuming call.
+    pub(crate) fn retain_live(&mut self, live: &HashSet<AbstractIdentifier>) {
+        self.strings_tracked.retain(|id, _| live.contains(id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abstract_domain::BricksDomain;
+    use crate::intermediate_representation::{ByteSize, Variable};
+
+    fn id(name: &str) -> AbstractIdentifier {
+        super::super::identifier::register_id(&Variable {
+            name: name.to_string(),
+            size: ByteSize::new(8),
+            is_temp: false,
+        })
+    }
+
+    #[test]
+    fn one_sided_identifier_merges_to_top() {
+        let tracked_id = id("rdi");
+        let mut with_value = State::new();
+        with_value.set(tracked_id.clone(), BricksDomain::from("safe".to_string()));
+
+        let mut without_value = State::new();
+        without_value.set_top(&tracked_id);
+
+        let merged = with_value.merge(&without_value);
+        assert_eq!(merged.get(&tracked_id), None);
+        assert!(merged.is_top());
+    }
+
+    #[test]
+    fn shared_identifier_merges_values() {
+        let tracked_id = id("rdi");
+        let mut first = State::new();
+        first.set(tracked_id.clone(), BricksDomain::from("ab".to_string()));
+        let mut second = State::new();
+        second.set(tracked_id.clone(), BricksDomain::from("ab".to_string()));
+
+        let merged = first.merge(&second);
+        assert_eq!(
+            merged.get(&tracked_id),
+            Some(&BricksDomain::from("ab".to_string()))
+        );
+    }
 }